@@ -1,11 +1,20 @@
 //! Gaussian processes.
 
-use complex::{Complex, c64};
+use complex::Complex;
 use probability::distribution::{Distribution, Gaussian};
 use probability::generator::Generator;
 
 use {Path, Process, Stationary};
 
+pub mod embedding;
+pub mod field;
+pub mod kernel;
+pub mod regression;
+
+pub use self::embedding::{Diagnostic, Embedding, Error};
+pub use self::field::Field;
+pub use self::regression::Posterior;
+
 /// A fractional Gaussian noise.
 pub struct FractionalNoise {
     hurst: f64,
@@ -54,7 +63,9 @@ impl FractionalNoisePath {
             data: {
                 let gaussian = Gaussian::new(0.0, 1.0);
                 let scale = (1.0 / (size - 1) as f64).powf(noise.hurst);
-                let data = circulant_embedding(noise, size, || gaussian.sample(generator));
+                let (data, _) = embedding::embed(
+                    size, |tau| Stationary::cov(noise, tau), || gaussian.sample(generator),
+                    &Embedding::Approximate).expect("approximate embedding never fails");
                 data.iter().take(size).map(|point| scale * point.re()).collect()
             },
         }
@@ -90,61 +101,98 @@ impl Iterator for FractionalNoisePath {
     }
 }
 
-/// Compute two independent sample paths stored in the real and complex parts of
-/// a sequence of `2 × n` complex numbers.
-///
-/// References:
-///
-/// 1. Dirk P. Kroese, Thomas Taimre, and Zdravko I. Botev. Handbook for Monte
-///    Carlo Methods. Hoboken, N.J.: Wiley, 2011.
-///
-/// 2. C. R. Dietrich and G. N. Newsam. “Fast and Exact Simulation of Stationary
-///    Gaussian Processes Through Circulant Embedding of the Covariance Matrix.”
-///    Siam Journal on Scientific Computing, 1997.
-fn circulant_embedding<P, F>(process: &P, n: usize, mut gaussian: F) -> Vec<c64>
-    where P: Process<Index=usize, State=f64> + Stationary<Index=usize>, F: FnMut() -> f64
-{
-    use czt;
-
-    macro_rules! chirp(
-        ($m:expr) => ({
-            use std::f64::consts::PI;
-            c64::from_polar(1.0, -2.0 * PI / $m as f64)
-        });
-    );
-
-    let m = (1 + n) + (1 + n) - 2;
-    let mut data = vec![0.0; m];
+/// A stationary Gaussian process defined by an arbitrary covariance kernel.
+pub struct StationaryGaussian<S> {
+    kernel: S,
+    embedding: Embedding,
+}
+
+/// A sample path of a `StationaryGaussian` process.
+pub struct StationaryGaussianPath {
+    position: usize,
+    data: Vec<f64>,
+}
+
+impl<S> StationaryGaussian<S> where S: Stationary<Distance = f64> {
+    /// Create a stationary Gaussian process, clamping any negative eigenvalues
+    /// encountered during embedding.
+    ///
+    /// Use `with_embedding` to grow the embedding until it is exact instead.
+    #[inline]
+    pub fn new(kernel: S) -> StationaryGaussian<S> {
+        StationaryGaussian::with_embedding(kernel, Embedding::Approximate)
+    }
+
+    /// Create a stationary Gaussian process with an explicit non-negative-
+    /// definiteness strategy; see `Embedding`.
+    #[inline]
+    pub fn with_embedding(kernel: S, embedding: Embedding) -> StationaryGaussian<S> {
+        StationaryGaussian { kernel: kernel, embedding: embedding }
+    }
+
+    /// Generate a sample path on a uniform grid of `count` points spaced `spacing`
+    /// apart, starting at zero.
+    #[inline]
+    pub fn sample<G>(&self, count: usize, spacing: f64, generator: &mut G)
+        -> Result<StationaryGaussianPath, Error>
+        where G: Generator
     {
-        data[0] = Stationary::cov(process, 0);
-        for i in 1..(n + 1) {
-            data[i] = Stationary::cov(process, i);
-            data[m - i] = data[i];
-        }
+        self.sample_pair(count, spacing, generator).map(|(first, _, _)| first)
     }
 
-    let mut data = czt::forward(&data, m, chirp!(m), c64(1.0, 0.0));
+    /// Generate two independent sample paths from a single fast-Fourier-transform
+    /// pass, reusing the imaginary part that `embedding::embed` already computes
+    /// alongside the real one, together with the resulting embedding diagnostic.
+    pub fn sample_pair<G>(&self, count: usize, spacing: f64, generator: &mut G)
+        -> Result<(StationaryGaussianPath, StationaryGaussianPath, Diagnostic), Error>
+        where G: Generator
     {
-        let scale = 1.0 / (2 * n) as f64;
-        for i in 0..m {
-            if cfg!(debug_assertions) {
-                const EPSILON: f64 = 1e-10;
-                assert!(data[i].re() > -EPSILON);
-                assert!(data[i].im().abs() < EPSILON);
-            }
-            let sigma = (data[i].re().max(0.0) * scale).sqrt();
-            data[i] = c64(sigma * gaussian(), sigma * gaussian());
-        }
+        debug_assert!(count > 0 && spacing > 0.0);
+        let n = if count > 1 { count - 1 } else { 1 };
+        let gaussian = Gaussian::new(0.0, 1.0);
+        let (data, diagnostic) = try!(embedding::embed(
+            n, |tau| Stationary::cov(&self.kernel, tau as f64 * spacing),
+            || gaussian.sample(generator), &self.embedding));
+        let first = data.iter().take(count).map(|point| point.re()).collect();
+        let second = data.iter().take(count).map(|point| point.im()).collect();
+        Ok((StationaryGaussianPath::new(first), StationaryGaussianPath::new(second), diagnostic))
     }
+}
+
+impl StationaryGaussianPath {
+    #[inline]
+    fn new(data: Vec<f64>) -> StationaryGaussianPath {
+        StationaryGaussianPath { position: 0, data: data }
+    }
+}
+
+impl Path<f64> for StationaryGaussianPath {
+}
+
+impl Iterator for StationaryGaussianPath {
+    type Item = f64;
 
-    czt::forward(&mut data, m, chirp!(m), c64(1.0, 0.0))
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.data.len() {
+            None
+        } else {
+            let state = self.data[self.position];
+            self.position += 1;
+            Some(state)
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use assert;
     use complex::Complex;
-    use gaussian::FractionalNoise;
+
+    use Stationary;
+    use gaussian::{Embedding, FractionalNoise};
+    use gaussian::embedding;
+    use gaussian::kernel::SquaredExponential;
 
     #[test]
     fn circulant_embedding() {
@@ -288,7 +336,8 @@ mod tests {
         let process = FractionalNoise::new(hurst);
 
         let n = 42;
-        let data = super::circulant_embedding(&process, n, gaussian);
+        let (data, _) = embedding::embed(
+            n, |tau| Stationary::cov(&process, tau), gaussian, &Embedding::Approximate).unwrap();
 
         let mut sum = 0.0;
         let scale = (n as f64).powf(-hurst);
@@ -299,4 +348,50 @@ mod tests {
 
         assert::close(&data, &expected_data[..], 1e-13);
     }
+
+    #[test]
+    fn stationary_gaussian_sample_pair() {
+        // `StationaryGaussian::sample_pair` is a thin wrapper around the same
+        // `embedding::embed` core exercised by `circulant_embedding` above, so this
+        // pins it the same way: feed fixed Gaussian draws through the kernel's
+        // covariance and check both the real and the reused imaginary path.
+        let gaussians = [
+             8.102932976201541e-01, -2.384069272551717e-01,
+             8.090132863109721e-01,  1.443789052696852e+00,
+            -6.342011099791740e-01,  4.659107078924064e-01,
+             4.798851709794495e-01,  9.031053603908425e-01,
+            -1.246037135239802e+00, -8.150638461766888e-01,
+            -7.606671219140951e-01,  9.695826024165091e-01,
+             1.261981940702712e-02,  7.307141568138659e-01,
+            -9.931071802590555e-01,  1.206415661093461e+00,
+            -4.651247130994393e-01, -1.659671326157153e+00,
+            -5.743790846202859e-01, -1.577003886736841e+00,
+        ];
+
+        let expected_first = [
+             9.174140642384705e-01, 2.172238353466236e+00, 2.675794947651500e+00,
+             2.648707320175591e+00, 1.723188139143468e+00, 9.694431768532208e-01,
+        ];
+        let expected_second = [
+             6.507679113606724e-02, -1.233119915096126e+00, -1.085246132477924e+00,
+            -7.684831660367819e-01, -6.879991034288508e-01, -6.202335946528027e-01,
+        ];
+
+        let kernel = SquaredExponential::new(2.0, 1.5);
+        let spacing = 0.5;
+        let count = 6;
+
+        let mut k = 0;
+        let gaussian = || { k += 1; gaussians[k - 1] };
+
+        let (data, _) = embedding::embed(
+            count - 1, |tau| Stationary::cov(&kernel, tau as f64 * spacing), gaussian,
+            &Embedding::Approximate).unwrap();
+
+        let first = data.iter().take(count).map(|point| point.re()).collect::<Vec<_>>();
+        let second = data.iter().take(count).map(|point| point.im()).collect::<Vec<_>>();
+
+        assert::close(&first, &expected_first[..], 1e-13);
+        assert::close(&second, &expected_second[..], 1e-13);
+    }
 }
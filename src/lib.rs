@@ -54,3 +54,10 @@ impl Distance for usize {
         0
     }
 }
+
+impl Distance for f64 {
+    #[inline(always)]
+    fn zero() -> f64 {
+        0.0
+    }
+}